@@ -0,0 +1,298 @@
+// A pluggable subsystem for the encodings a SysEx dump can show up in.
+// `get_format` resolves a command-line name to a `Format` impl.
+
+use syxpack::{Message, message_count, split_messages};
+
+// A System Exclusive encoding that can be parsed into messages and
+// rendered back out again.
+pub trait Format {
+    // The name used to select this format on the command line.
+    fn name(&self) -> &'static str;
+
+    // Parses a buffer in this format into the messages it contains.
+    fn parse(&self, data: &[u8]) -> Vec<Message>;
+
+    // Renders a sequence of messages into this format.
+    fn render(&self, messages: &[Message]) -> Vec<u8>;
+}
+
+// Splits a raw buffer of one or more `F0 ... F7` messages into `Message`s.
+fn messages_from_syx_bytes(data: &[u8]) -> Vec<Message> {
+    let mut messages = Vec::new();
+    if message_count(data) == 1 {
+        if let Ok(message) = Message::new(data) {
+            messages.push(message);
+        }
+    }
+    else {
+        for chunk in split_messages(data.to_vec()) {
+            if let Ok(message) = Message::new(&chunk) {
+                messages.push(message);
+            }
+        }
+    }
+    messages
+}
+
+// Concatenates the raw bytes (with `F0`/`F7` delimiters) of each message.
+fn syx_bytes_from_messages(messages: &[Message]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for message in messages {
+        data.extend(message.to_bytes());
+    }
+    data
+}
+
+// Raw `.syx` bytes, with `F0`/`F7` delimiters already in place.
+pub struct SyxFormat;
+
+impl Format for SyxFormat {
+    fn name(&self) -> &'static str { "syx" }
+
+    fn parse(&self, data: &[u8]) -> Vec<Message> {
+        messages_from_syx_bytes(data)
+    }
+
+    fn render(&self, messages: &[Message]) -> Vec<u8> {
+        syx_bytes_from_messages(messages)
+    }
+}
+
+// Space-separated two-digit hex bytes, e.g. `F0 42 30 F7`.
+pub struct HexFormat;
+
+impl Format for HexFormat {
+    fn name(&self) -> &'static str { "hex" }
+
+    fn parse(&self, data: &[u8]) -> Vec<Message> {
+        let text = String::from_utf8_lossy(data);
+        let mut bytes = Vec::new();
+        for token in text.split_whitespace() {
+            if let Ok(b) = u8::from_str_radix(token, 16) {
+                bytes.push(b);
+            }
+        }
+        messages_from_syx_bytes(&bytes)
+    }
+
+    fn render(&self, messages: &[Message]) -> Vec<u8> {
+        let bytes = syx_bytes_from_messages(messages);
+        let hex_string = bytes.iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<String>>()
+            .join(" ");
+        hex_string.into_bytes()
+    }
+}
+
+// Base64-encoded raw bytes.
+pub struct Base64Format;
+
+impl Format for Base64Format {
+    fn name(&self) -> &'static str { "base64" }
+
+    fn parse(&self, data: &[u8]) -> Vec<Message> {
+        let text = String::from_utf8_lossy(data);
+        match base64::decode(text.trim()) {
+            Ok(bytes) => messages_from_syx_bytes(&bytes),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn render(&self, messages: &[Message]) -> Vec<u8> {
+        let bytes = syx_bytes_from_messages(messages);
+        base64::encode(&bytes).into_bytes()
+    }
+}
+
+// The ReceiveMIDI/SendMIDI `system-exclusive hex|dec <bytes...>` line format.
+pub struct ReceiveMidiFormat;
+
+impl Format for ReceiveMidiFormat {
+    fn name(&self) -> &'static str { "receivemidi" }
+
+    fn parse(&self, data: &[u8]) -> Vec<Message> {
+        let text = String::from_utf8_lossy(data);
+        let mut messages = Vec::new();
+
+        for line in text.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            // We want at least "system-exclusive", "hex" or "dec", and one byte.
+            if parts.len() < 3 || parts[0] != "system-exclusive" {
+                continue;
+            }
+
+            let base = if parts[1] == "hex" { 16 } else { 10 };
+
+            let mut payload: Vec<u8> = Vec::new();
+            for part in &parts[2..] {
+                if let Ok(b) = u8::from_str_radix(part, base) {
+                    payload.push(b);
+                }
+            }
+
+            payload.insert(0, 0xf0);
+            payload.push(0xf7);
+
+            if let Ok(message) = Message::new(&payload) {
+                messages.push(message);
+            }
+        }
+
+        messages
+    }
+
+    fn render(&self, messages: &[Message]) -> Vec<u8> {
+        render_receivemidi_lines(messages, false).join("\n").into_bytes()
+    }
+}
+
+// Renders each message as a `system-exclusive hex|dec <bytes...>` line.
+// Shared with `syx send`, which lets the caller pick the radix.
+pub fn render_receivemidi_lines(messages: &[Message], decimal: bool) -> Vec<String> {
+    messages.iter().map(|message| {
+        let bytes = message.to_bytes();
+        let inner = &bytes[1..bytes.len() - 1]; // drop F0/F7
+        let (radix_name, byte_string) = if decimal {
+            ("dec", inner.iter().map(|b| b.to_string()).collect::<Vec<String>>().join(" "))
+        }
+        else {
+            ("hex", inner.iter().map(|b| format!("{:02X}", b)).collect::<Vec<String>>().join(" "))
+        };
+        format!("system-exclusive {} {}", radix_name, byte_string)
+    }).collect()
+}
+
+// A C/Rust `const` byte array initializer. Parsing accepts both the C form
+// `const uint8_t DATA[] = { ... };` and the Rust form
+// `const DATA: [u8; N] = [ ... ];`; rendering emits the C form.
+pub struct CArrayFormat;
+
+impl Format for CArrayFormat {
+    fn name(&self) -> &'static str { "carray" }
+
+    fn parse(&self, data: &[u8]) -> Vec<Message> {
+        let text = String::from_utf8_lossy(data);
+        let mut bytes = Vec::new();
+        for token in text.split(|c: char| c == ',' || c == '{' || c == '}' || c == '[' || c == ']' || c == ';' || c.is_whitespace()) {
+            // Only explicit `0x`/`0X` literals are byte values; anything
+            // else (`const`, `uint8_t`, `DATA[]`, `=`, ...) is skipped.
+            let hex_digits = match token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+                Some(digits) => digits,
+                None => continue,
+            };
+            if let Ok(b) = u8::from_str_radix(hex_digits, 16) {
+                bytes.push(b);
+            }
+        }
+        messages_from_syx_bytes(&bytes)
+    }
+
+    fn render(&self, messages: &[Message]) -> Vec<u8> {
+        let bytes = syx_bytes_from_messages(messages);
+        let entries = bytes.iter()
+            .map(|b| format!("0x{:02X}", b))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("const uint8_t DATA[] = {{ {} }};\n", entries).into_bytes()
+    }
+}
+
+// Resolves a command-line format name to its `Format` implementation.
+pub fn get_format(name: &str) -> Option<Box<dyn Format>> {
+    match name {
+        "syx" => Some(Box::new(SyxFormat)),
+        "hex" => Some(Box::new(HexFormat)),
+        "base64" => Some(Box::new(Base64Format)),
+        "receivemidi" => Some(Box::new(ReceiveMidiFormat)),
+        "carray" => Some(Box::new(CArrayFormat)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syxpack::Manufacturer;
+
+    fn sample_message() -> Message {
+        Message::ManufacturerSpecific {
+            manufacturer: Manufacturer::new(vec![0x42]).unwrap(),
+            payload: vec![0x01, 0x02, 0x03],
+        }
+    }
+
+    fn payloads(messages: &[Message]) -> Vec<Vec<u8>> {
+        messages.iter().map(|message| match message {
+            Message::ManufacturerSpecific { payload, .. } => payload.clone(),
+            Message::Universal { payload, .. } => payload.clone(),
+        }).collect()
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let format = HexFormat;
+        let rendered = format.render(&[sample_message()]);
+        let parsed = format.parse(&rendered);
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn hex_skips_malformed_tokens() {
+        let format = HexFormat;
+        let parsed = format.parse(b"F0 42 ZZ 01 02 03 F7");
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn base64_round_trips() {
+        let format = Base64Format;
+        let rendered = format.render(&[sample_message()]);
+        let parsed = format.parse(&rendered);
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn base64_returns_no_messages_for_invalid_input() {
+        let format = Base64Format;
+        assert!(format.parse(b"not valid base64!!").is_empty());
+    }
+
+    #[test]
+    fn carray_round_trips() {
+        let format = CArrayFormat;
+        let rendered = format.render(&[sample_message()]);
+        let parsed = format.parse(&rendered);
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn carray_ignores_keywords_without_a_0x_prefix() {
+        let format = CArrayFormat;
+        let parsed = format.parse(b"const uint8_t DATA[] = { 0xF0, 0x42, 0x30, 0xF7 };\n");
+        assert_eq!(payloads(&parsed), vec![vec![0x30]]);
+    }
+
+    #[test]
+    fn receivemidi_round_trips_hex() {
+        let format = ReceiveMidiFormat;
+        let rendered = format.render(&[sample_message()]);
+        assert!(String::from_utf8(rendered.clone()).unwrap().starts_with("system-exclusive hex"));
+        let parsed = format.parse(&rendered);
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn receivemidi_parses_decimal_radix() {
+        let format = ReceiveMidiFormat;
+        let parsed = format.parse(b"system-exclusive dec 66 1 2 3");
+        assert_eq!(payloads(&parsed), vec![vec![0x01, 0x02, 0x03]]);
+    }
+
+    #[test]
+    fn render_receivemidi_lines_honors_decimal_flag() {
+        let lines = render_receivemidi_lines(&[sample_message()], true);
+        assert_eq!(lines, vec!["system-exclusive dec 66 1 2 3"]);
+    }
+}
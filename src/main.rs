@@ -18,9 +18,30 @@ use syxpack::{
     find_manufacturer
 };
 use clap::{
-    Parser, 
-    Subcommand
+    Parser,
+    Subcommand,
+    ValueEnum
 };
+use serde::Serialize;
+
+mod formats;
+use formats::{get_format, render_receivemidi_lines};
+
+mod signatures;
+use signatures::SignatureDatabase;
+
+mod stats;
+use stats::Stats;
+
+mod manifest;
+use manifest::{Manifest, ManifestEntry};
+
+// The output format shared by `identify` and `sections`.
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -31,10 +52,18 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    // Identifies the messages in the SysEx file.
+    // Identifies the messages in the SysEx file. With `--match`, also looks
+    // up each message in a signature database to recognize the originating
+    // device and dump type.
     Identify {
         #[arg(short, long)]
         file: PathBuf,
+
+        #[arg(long = "match", value_name = "DATABASE")]
+        database: Option<PathBuf>,
+
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
 
     // Extracts the payload from the SysEx file.
@@ -53,6 +82,12 @@ enum Commands {
 
         #[arg(short, long)]
         verbose: bool,
+
+        // Path to write an mtree-style manifest (or JSON, with a `.json`
+        // extension) listing the name, length, offset, and MD5 digest of
+        // each emitted file, plus the digest of the whole source file.
+        #[arg(long)]
+        manifest: Option<PathBuf>,
     },
 
     // Generates information about sections in the SysEx file.
@@ -60,12 +95,28 @@ enum Commands {
         #[arg(short, long)]
         file: PathBuf,
 
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
 
     // Receive SysEx messages from stdin in the ReceiveMIDI format.
     Receive {
     },
 
+    // Prints SendMIDI-compatible `system-exclusive hex|dec <bytes...>`
+    // lines for one file or every `.syx` file in a directory, so the
+    // output can be piped straight into a hardware-sending tool.
+    Send {
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        #[arg(short, long)]
+        dir: Option<PathBuf>,
+
+        #[arg(long)]
+        decimal: bool,
+    },
+
     // Makes a manufacturer-specific SysEx message for the given manufacturer,
     // with the specified payload.
     Make {
@@ -77,6 +128,61 @@ enum Commands {
 
         #[arg(short, long)]
         outfile: PathBuf,  // name of output file
+    },
+
+    // Converts a SysEx dump from one format to another, e.g. raw `.syx`
+    // bytes to a hex dump, or a ReceiveMIDI line to a C array initializer.
+    // Supported formats: syx, hex, base64, receivemidi, carray.
+    Convert {
+        #[arg(long)]
+        from: String,
+
+        #[arg(long)]
+        to: String,
+
+        #[arg(short, long)]
+        infile: PathBuf,
+
+        #[arg(short, long)]
+        outfile: PathBuf,
+    },
+
+    // Generates and manages device/patch signature databases.
+    Signatures {
+        #[command(subcommand)]
+        command: SignaturesCommands,
+    },
+
+    // Reports a byte-frequency histogram, size distribution, and 7-bit
+    // validity check across all messages in the file.
+    Stats {
+        #[arg(short, long)]
+        file: PathBuf,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    // Re-reads the files listed in a split manifest and confirms their
+    // sizes and digests still match, reporting any missing or altered files.
+    Verify {
+        #[arg(long)]
+        manifest: PathBuf,
+    }
+}
+
+#[derive(Subcommand)]
+enum SignaturesCommands {
+    // Generates a signature from one or more example SysEx files, by
+    // diffing their payload prefixes, and adds it to the database.
+    Generate {
+        #[arg(short, long)]
+        name: String,
+
+        #[arg(short, long)]
+        database: PathBuf,
+
+        files: Vec<PathBuf>,
     }
 }
 
@@ -84,16 +190,30 @@ fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Identify { file } => run_identify(file),
+        Commands::Identify { file, database, format } => run_identify(file, database, *format),
         Commands::Extract { infile, outfile } => run_extract(infile, outfile),
-        Commands::Split { file, verbose } => run_split(file, *verbose),
-        Commands::Sections { file } => run_sections(file),
+        Commands::Split { file, verbose, manifest } => run_split(file, *verbose, manifest),
+        Commands::Sections { file, format } => run_sections(file, *format),
         Commands::Receive { } => run_receive(),
-        Commands::Make { manufacturer, payload, outfile } => run_make(manufacturer, payload, outfile)
+        Commands::Send { file, dir, decimal } => run_send(file, dir, *decimal),
+        Commands::Make { manufacturer, payload, outfile } => run_make(manufacturer, payload, outfile),
+        Commands::Convert { from, to, infile, outfile } => run_convert(from, to, infile, outfile),
+        Commands::Signatures { command } => match command {
+            SignaturesCommands::Generate { name, database, files } => run_signatures_generate(name, database, files),
+        },
+        Commands::Stats { file, json } => run_stats(file, *json),
+        Commands::Verify { manifest } => run_verify(manifest),
     }
 }
 
-fn run_identify(file: &PathBuf) {
+fn run_identify(file: &PathBuf, database: &Option<PathBuf>, format: OutputFormat) {
+    let signature_db = database.as_ref().map(|path| {
+        SignatureDatabase::load(path).unwrap_or_else(|| {
+            eprintln!("Unable to read signature database {}", path.display());
+            std::process::exit(1);
+        })
+    });
+
     if let Some(buffer) = read_file(file) {
         let mut all_messages: Vec<Message> = Vec::new();
         let count = message_count(&buffer);
@@ -109,19 +229,137 @@ fn run_identify(file: &PathBuf) {
             }
         };
 
+        let mut identified: Vec<IdentifiedMessage> = Vec::new();
+
         let mut number = 1;
-        for message in all_messages {
-            if count > 1 {
-                println!("Message {} of {}", number, count);
+        for message in &all_messages {
+            let signature = signature_db.as_ref().and_then(|db| db.identify(message));
+
+            match format {
+                OutputFormat::Text => {
+                    if count > 1 {
+                        println!("Message {} of {}", number, count);
+                    }
+                    identify(message);
+                    println!("MD5 digest: {:x}", message.digest());
+
+                    if signature_db.is_some() {
+                        match &signature {
+                            Some(found) => println!("Signature: {} ({} matching bytes)", found.name, found.score),
+                            None => println!("Signature: unknown"),
+                        }
+                    }
+
+                    println!();
+                },
+                OutputFormat::Json => {
+                    identified.push(to_identified_message(message, number, count, signature.map(IdentifiedSignature::from)));
+                }
             }
-            identify(&message);
-            println!("MD5 digest: {:x}", message.digest());
-            println!();
+
             number += 1;
         }
+
+        if let OutputFormat::Json = format {
+            println!("{}", serde_json::to_string_pretty(&identified).expect("serialize identified messages"));
+        }
+    }
+}
+
+fn to_identified_message(message: &Message, index: usize, count: usize, signature: Option<IdentifiedSignature>) -> IdentifiedMessage {
+    match message {
+        Message::ManufacturerSpecific { manufacturer, payload } => IdentifiedMessage {
+            index,
+            count,
+            manufacturer_id: Some(hex::encode(manufacturer.to_bytes())),
+            manufacturer_name: Some(manufacturer.to_string()),
+            manufacturer_group: Some(manufacturer.group().to_string()),
+            universal_kind: None,
+            universal_target: None,
+            universal_sub_id1: None,
+            universal_sub_id2: None,
+            payload_length: payload.len(),
+            digest: format!("{:x}", message.digest()),
+            signature,
+        },
+        Message::Universal { kind, target, sub_id1, sub_id2, payload } => IdentifiedMessage {
+            index,
+            count,
+            manufacturer_id: None,
+            manufacturer_name: None,
+            manufacturer_group: None,
+            universal_kind: Some(kind.to_string()),
+            universal_target: Some(target.to_string()),
+            universal_sub_id1: Some(*sub_id1),
+            universal_sub_id2: Some(*sub_id2),
+            payload_length: payload.len(),
+            digest: format!("{:x}", message.digest()),
+            signature,
+        },
+    }
+}
+
+fn run_stats(file: &PathBuf, json: bool) {
+    if let Some(buffer) = read_file(file) {
+        let count = message_count(&buffer);
+        let all_messages: Vec<Message> = if count == 1 {
+            Message::new(&buffer).ok().into_iter().collect()
+        }
+        else {
+            split_messages(buffer.to_vec()).iter()
+                .filter_map(|chunk| Message::new(chunk).ok())
+                .collect()
+        };
+
+        let stats = Stats::gather(&all_messages);
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&stats).expect("serialize stats"));
+        }
+        else {
+            stats.print_table();
+        }
     }
 }
 
+fn run_signatures_generate(name: &String, database: &PathBuf, files: &Vec<PathBuf>) {
+    if files.is_empty() {
+        eprintln!("Please provide at least one example SysEx file");
+        std::process::exit(1);
+    }
+
+    let mut examples: Vec<Message> = Vec::new();
+    for file in files {
+        match read_file(file) {
+            Some(buffer) => match Message::new(&buffer) {
+                Ok(message) => examples.push(message),
+                Err(e) => {
+                    eprintln!("Error reading {}: {:?}", file.display(), e);
+                    std::process::exit(1);
+                }
+            },
+            None => {
+                eprintln!("Unable to read {}", file.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let signature = match signatures::Signature::generate(name, &examples) {
+        Some(signature) => signature,
+        None => {
+            eprintln!("Unable to generate a signature from the given examples");
+            std::process::exit(1);
+        }
+    };
+
+    let mut db = SignatureDatabase::load(database).unwrap_or_default();
+    db.signatures.push(signature);
+
+    db.save(database).expect("write signature database");
+    println!("Added signature '{}' to {}", name, database.display());
+}
+
 fn identify(message: &Message) {
     match message {
         Message::ManufacturerSpecific { manufacturer, payload } => {
@@ -138,29 +376,31 @@ fn identify(message: &Message) {
 
 fn run_extract(infile: &PathBuf, outfile: &PathBuf) {
     if let Some(buffer) = read_file(infile) {
-        if message_count(&buffer) > 1 {
+        let messages = get_format("syx").unwrap().parse(&buffer);
+
+        if messages.len() > 1 {
             println!("More than one System Exclusive message found in file. Please use `syx split` to separate them.");
+            return;
         }
-        else {
-            match Message::new(&buffer) {
-                // At this point, the SysEx delimiters and the manufacturer byte(s)
-                // have already been stripped off. What's left is the payload.
-                // For example, if the original message was "F0 42 30 28 54 02 ... 5C F7",
-                // then the payload is "30 28 54 02 ... 5C".
-                Ok(Message::ManufacturerSpecific { payload, .. })
-                | Ok(Message::Universal { payload, .. }) => {
-                    let mut f = fs::File::create(&outfile).expect("create file");
-                    f.write_all(&payload).expect("write to output file");
-                },
-                Err(e) => {
-                    eprintln!("Error: {:?}", e);
-                }
-            };
+
+        match messages.first() {
+            // At this point, the SysEx delimiters and the manufacturer byte(s)
+            // have already been stripped off. What's left is the payload.
+            // For example, if the original message was "F0 42 30 28 54 02 ... 5C F7",
+            // then the payload is "30 28 54 02 ... 5C".
+            Some(Message::ManufacturerSpecific { payload, .. })
+            | Some(Message::Universal { payload, .. }) => {
+                let mut f = fs::File::create(&outfile).expect("create file");
+                f.write_all(payload).expect("write to output file");
+            },
+            None => {
+                eprintln!("Error: no System Exclusive message found in file");
+            }
         }
     }
 }
 
-fn run_split(file: &PathBuf, verbose: bool) {
+fn run_split(file: &PathBuf, verbose: bool, manifest: &Option<PathBuf>) {
     if let Some(buffer) = read_file(file) {
         let count = message_count(&buffer);
 
@@ -175,6 +415,9 @@ fn run_split(file: &PathBuf, verbose: bool) {
 
         if count > 1 {
             let messages = split_messages(buffer.to_vec());
+            let mut entries: Vec<ManifestEntry> = Vec::new();
+            let mut offset = 0usize;
+
             for (i, message) in messages.iter().enumerate() {
                 let output_filename = format!(
                     "{}-{:0>3}.{}",
@@ -184,14 +427,88 @@ fn run_split(file: &PathBuf, verbose: bool) {
                 if verbose {
                     println!("Writing {}", output_filename);
                 }
-                let mut file = fs::File::create(output_filename)
+                let mut output_file = fs::File::create(&output_filename)
                     .expect("unable to create file");
-                file.write_all(message).expect("unable to write file");
+                output_file.write_all(message).expect("unable to write file");
+
+                if manifest.is_some() {
+                    let digest = match Message::new(message) {
+                        Ok(parsed) => format!("{:x}", parsed.digest()),
+                        Err(_) => format!("{:x}", md5::compute(message)),
+                    };
+                    entries.push(ManifestEntry {
+                        name: output_filename,
+                        length: message.len(),
+                        offset,
+                        digest,
+                    });
+                }
+
+                offset += message.len();
+            }
+
+            if let Some(manifest_path) = manifest {
+                let source_digest = format!("{:x}", md5::compute(&buffer));
+                let manifest = Manifest { source_digest, entries };
+                manifest.save(manifest_path).expect("write manifest");
+                if verbose {
+                    println!("Wrote manifest {}", manifest_path.display());
+                }
             }
         }
     }
 }
 
+fn run_verify(manifest_path: &PathBuf) {
+    let manifest = match Manifest::load(manifest_path) {
+        Some(manifest) => manifest,
+        None => {
+            eprintln!("Unable to read manifest {}", manifest_path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let mut ok = true;
+
+    for entry in &manifest.entries {
+        let path = Path::new(&entry.name);
+        match fs::read(path) {
+            Ok(data) => {
+                let mut problems = Vec::new();
+
+                if data.len() != entry.length {
+                    problems.push(format!("length {} != expected {}", data.len(), entry.length));
+                }
+
+                let digest = match Message::new(&data) {
+                    Ok(parsed) => format!("{:x}", parsed.digest()),
+                    Err(_) => format!("{:x}", md5::compute(&data)),
+                };
+                if digest != entry.digest {
+                    problems.push(format!("digest {} != expected {}", digest, entry.digest));
+                }
+
+                if problems.is_empty() {
+                    println!("OK      {}", entry.name);
+                }
+                else {
+                    ok = false;
+                    println!("FAILED  {} ({})", entry.name, problems.join(", "));
+                }
+            },
+            Err(_) => {
+                ok = false;
+                println!("MISSING {}", entry.name);
+            }
+        }
+    }
+
+    if !ok {
+        std::process::exit(1);
+    }
+}
+
+#[derive(Serialize)]
 enum SectionKind {
     Initiator,
     Manufacturer,
@@ -212,6 +529,7 @@ impl fmt::Display for SectionKind {
     }
 }
 
+#[derive(Serialize)]
 struct MessageSection {
     kind: SectionKind,
     name: String,
@@ -219,90 +537,190 @@ struct MessageSection {
     length: usize,  // length of section in bytes
 }
 
-fn run_sections(file: &PathBuf) {
+// The JSON representation of a signature database match, carrying both the
+// matched name and its confidence score.
+#[derive(Serialize)]
+struct IdentifiedSignature {
+    name: String,
+    score: usize,
+}
+
+impl From<signatures::SignatureMatch> for IdentifiedSignature {
+    fn from(found: signatures::SignatureMatch) -> Self {
+        IdentifiedSignature { name: found.name, score: found.score }
+    }
+}
+
+// The JSON representation of an identified message, carrying the same
+// information `identify` otherwise prints as free-form text.
+#[derive(Serialize)]
+struct IdentifiedMessage {
+    index: usize,
+    count: usize,
+    manufacturer_id: Option<String>,
+    manufacturer_name: Option<String>,
+    manufacturer_group: Option<String>,
+    universal_kind: Option<String>,
+    universal_target: Option<String>,
+    universal_sub_id1: Option<u8>,
+    universal_sub_id2: Option<u8>,
+    payload_length: usize,
+    digest: String,
+    signature: Option<IdentifiedSignature>,
+}
+
+// The Universal message header (kind/target/sub-IDs), carried alongside a
+// message's section layout since `sections_of` otherwise only knows
+// offsets and lengths.
+#[derive(Serialize)]
+struct UniversalHeader {
+    kind: String,
+    target: String,
+    sub_id1: u8,
+    sub_id2: u8,
+}
+
+// The JSON representation of one message's section layout, for `sections`.
+#[derive(Serialize)]
+struct IdentifiedSections {
+    index: usize,
+    count: usize,
+    universal: Option<UniversalHeader>,
+    sections: Vec<MessageSection>,
+}
+
+fn run_sections(file: &PathBuf, format: OutputFormat) {
     if let Some(buffer) = read_file(file) {
-        if message_count(&buffer) > 1 {
-            println!("More than one System Exclusive message found in file. Please use `syx split` to separate them.");
-            std::process::exit(1);
+        let count = message_count(&buffer);
+        let chunks: Vec<Vec<u8>> = if count == 1 {
+            vec![buffer.to_vec()]
         }
+        else {
+            split_messages(buffer.to_vec())
+        };
 
-        let message = Message::new(&buffer);
-        let mut offset = 0;
+        let mut identified: Vec<IdentifiedSections> = Vec::new();
 
-        let mut sections: Vec<MessageSection> = Vec::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let (sections, universal) = sections_of(chunk);
 
-        sections.push(
-            MessageSection {
-                kind: SectionKind::Initiator,
-                name: "System Exclusive Initiator".to_string(),
-                offset: offset,
-                length: 1,
-            }
-        );
-
-        offset += 1;
-
-        match message {
-            Ok(Message::ManufacturerSpecific { manufacturer, payload }) => {
-                sections.push(
-                    MessageSection {
-                        kind: SectionKind::Manufacturer,
-                        name: "Manufacturer".to_string(),
-                        offset: offset,
-                        length: manufacturer.to_bytes().len(),
+            match format {
+                OutputFormat::Text => {
+                    if count > 1 {
+                        println!("Message {} of {}", index + 1, count);
                     }
-                );
-                offset += manufacturer.to_bytes().len();
-                sections.push(
-                    MessageSection {
-                        kind: SectionKind::Payload,
-                        name: "Message Payload".to_string(),
-                        offset: offset,
-                        length: payload.len(),
+                    if let Some(header) = &universal {
+                        println!("Universal, kind: {}, target: {}, Sub ID1: {:X} Sub ID2: {:X}",
+                            header.kind, header.target, header.sub_id1, header.sub_id2);
                     }
-                )
-            },
-            Ok(Message::Universal { kind, target, sub_id1, sub_id2, payload }) => {
-                sections.push(
-                    MessageSection {
-                        kind: SectionKind::Universal,
-                        name: "Universal".to_string(),
-                        offset: offset,
-                        length: 3,
+                    for section in &sections {
+                        println!("{:06X}: {} ({}, {} {})",
+                            section.offset,
+                            section.name,
+                            section.kind,
+                            section.length,
+                            if section.length == 1 { "byte" } else { "bytes" });
                     }
-                );
-
-                println!("Universal, kind: {}, target: {}, {:X} {:X}, payload = {} bytes",
-                    kind,
-                    target,
-                    sub_id1, sub_id2, payload.len());
-            },
-            Err(e) => {
-                println!("Error in message: {:?}", e);
+                    println!();
+                },
+                OutputFormat::Json => {
+                    identified.push(IdentifiedSections { index: index + 1, count, universal, sections });
+                }
             }
         }
 
-        sections.push(
-            MessageSection {
-                kind: SectionKind::Terminator,
-                name: "System Exclusive Terminator".to_string(),
-                offset: buffer.len() - 1,
-                length: 1,
-            }
-        );
-
-        for section in sections {
-            println!("{:06X}: {} ({}, {} {})", 
-                section.offset, 
-                section.name, 
-                section.kind, 
-                section.length,
-                if section.length == 1 { "byte" } else { "bytes" });
+        if let OutputFormat::Json = format {
+            println!("{}", serde_json::to_string_pretty(&identified).expect("serialize sections"));
+        }
+    }
+}
+
+// Computes the section layout (initiator, manufacturer/universal header,
+// payload, terminator) of a single raw SysEx message.
+fn sections_of(buffer: &[u8]) -> (Vec<MessageSection>, Option<UniversalHeader>) {
+    let message = Message::new(buffer);
+    let mut offset = 0;
+    let mut universal = None;
+
+    let mut sections: Vec<MessageSection> = Vec::new();
+
+    sections.push(
+        MessageSection {
+            kind: SectionKind::Initiator,
+            name: "System Exclusive Initiator".to_string(),
+            offset: offset,
+            length: 1,
+        }
+    );
+
+    offset += 1;
+
+    match message {
+        Ok(Message::ManufacturerSpecific { manufacturer, payload }) => {
+            sections.push(
+                MessageSection {
+                    kind: SectionKind::Manufacturer,
+                    name: "Manufacturer".to_string(),
+                    offset: offset,
+                    length: manufacturer.to_bytes().len(),
+                }
+            );
+            offset += manufacturer.to_bytes().len();
+            sections.push(
+                MessageSection {
+                    kind: SectionKind::Payload,
+                    name: "Message Payload".to_string(),
+                    offset: offset,
+                    length: payload.len(),
+                }
+            )
+        },
+        Ok(Message::Universal { kind, target, sub_id1, sub_id2, payload }) => {
+            sections.push(
+                MessageSection {
+                    kind: SectionKind::Universal,
+                    name: "Universal".to_string(),
+                    offset: offset,
+                    length: 3,
+                }
+            );
+            offset += 3;
+            sections.push(
+                MessageSection {
+                    kind: SectionKind::Payload,
+                    name: "Message Payload".to_string(),
+                    offset: offset,
+                    length: payload.len(),
+                }
+            );
+            universal = Some(UniversalHeader {
+                kind: kind.to_string(),
+                target: target.to_string(),
+                sub_id1,
+                sub_id2,
+            });
+        },
+        Err(e) => {
+            println!("Error in message: {:?}", e);
         }
     }
+
+    sections.push(
+        MessageSection {
+            kind: SectionKind::Terminator,
+            name: "System Exclusive Terminator".to_string(),
+            offset: buffer.len() - 1,
+            length: 1,
+        }
+    );
+
+    (sections, universal)
 }
 
 fn run_receive() {
+    let receivemidi = get_format("receivemidi").unwrap();
+    let syx = get_format("syx").unwrap();
+
     loop {
         let mut input = String::new();
         match std::io::stdin().read_line(&mut input) {
@@ -310,53 +728,31 @@ fn run_receive() {
                 return;
             }
             else {
-                let parts: Vec<&str> = input.split_whitespace().collect();
-
-                // We want at least "system-exclusive", "hex" or "dec", and one byte
-                if parts.len() < 3 {
+                let messages = receivemidi.parse(input.as_bytes());
+                if messages.is_empty() {
                     continue;
                 }
 
-                // Only deal with SysEx:
-                if parts[0] == "system-exclusive" {
-                    // Get the base of the byte strings.
-                    let base = if parts[1] == "hex" { 16 } else { 10 };
-
-                    let mut data: Vec<u8> = Vec::new();
-
-                    for part in &parts[2..] {
-                        match u8::from_str_radix(part, base) {
-                            Ok(b) => data.push(b),
-                            Err(_) => {
-                                //eprintln!("Error in byte string '{}': {}", part, e);
-                                continue;
-                            }
-                        }
-                    }
-
-                    // Add the MIDI System Exclusive delimiters:
-                    data.insert(0, 0xf0);
-                    data.push(0xf7);
-
-                    println!("Received {} bytes of System Exclusive data", data.len());
-
-                    // Write the data into a file named by the current timestamp.
-                    let now = SystemTime::now();
-                    let epoch_now = now
-                        .duration_since(UNIX_EPOCH)
-                        .expect("System time should be after Unix epoch");
-                    let filename = format!("{:?}.syx", epoch_now.as_secs());
-                    let path = Path::new(&filename);
-                    let display = path.display();
-                    let mut file = match fs::File::create(&path) {
-                        Err(why) => panic!("couldn't create {}: {}", display, why),
-                        Ok(file) => file,
-                    };
-
-                    match file.write_all(&data) {
-                        Err(why) => panic!("couldn't write to {}: {}", display, why),
-                        Ok(_) => { },
-                    }
+                let data = syx.render(&messages);
+
+                println!("Received {} bytes of System Exclusive data", data.len());
+
+                // Write the data into a file named by the current timestamp.
+                let now = SystemTime::now();
+                let epoch_now = now
+                    .duration_since(UNIX_EPOCH)
+                    .expect("System time should be after Unix epoch");
+                let filename = format!("{:?}.syx", epoch_now.as_secs());
+                let path = Path::new(&filename);
+                let display = path.display();
+                let mut file = match fs::File::create(&path) {
+                    Err(why) => panic!("couldn't create {}: {}", display, why),
+                    Ok(file) => file,
+                };
+
+                match file.write_all(&data) {
+                    Err(why) => panic!("couldn't write to {}: {}", display, why),
+                    Ok(_) => { },
                 }
             },
             Err(e) => {
@@ -367,6 +763,72 @@ fn run_receive() {
     }
 }
 
+fn run_convert(from: &String, to: &String, infile: &PathBuf, outfile: &PathBuf) {
+    let from_format = match get_format(from) {
+        Some(format) => format,
+        None => {
+            eprintln!("Unknown format '{}'. Supported formats: syx, hex, base64, receivemidi, carray", from);
+            std::process::exit(1);
+        }
+    };
+
+    let to_format = match get_format(to) {
+        Some(format) => format,
+        None => {
+            eprintln!("Unknown format '{}'. Supported formats: syx, hex, base64, receivemidi, carray", to);
+            std::process::exit(1);
+        }
+    };
+
+    let data = fs::read(infile).expect("read input file");
+    let messages = from_format.parse(&data);
+    if messages.is_empty() {
+        eprintln!("No System Exclusive messages found in input");
+        std::process::exit(1);
+    }
+
+    let output = to_format.render(&messages);
+    let mut f = fs::File::create(&outfile).expect("create file");
+    f.write_all(&output).expect("write to output file");
+}
+
+fn run_send(file: &Option<PathBuf>, dir: &Option<PathBuf>, decimal: bool) {
+    let files: Vec<PathBuf> = match (file, dir) {
+        (Some(file), None) => vec![file.clone()],
+        (None, Some(dir)) => {
+            let mut files: Vec<PathBuf> = fs::read_dir(dir)
+                .expect("read directory")
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("syx"))
+                .collect();
+            files.sort();
+            files
+        },
+        _ => {
+            eprintln!("Please provide exactly one of --file or --dir");
+            std::process::exit(1);
+        }
+    };
+
+    for path in &files {
+        if let Some(buffer) = read_file(path) {
+            let messages = if message_count(&buffer) == 1 {
+                Message::new(&buffer).ok().into_iter().collect()
+            }
+            else {
+                split_messages(buffer.to_vec()).iter()
+                    .filter_map(|chunk| Message::new(chunk).ok())
+                    .collect()
+            };
+
+            for line in render_receivemidi_lines(&messages, decimal) {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
 fn run_make(manufacturer: &String, payload: &String, outfile: &PathBuf) {
     match manufacturer.chars().nth(0).unwrap() {
         '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' => {
@@ -392,8 +854,9 @@ fn run_make(manufacturer: &String, payload: &String, outfile: &PathBuf) {
                     match hex::decode(payload) {
                         Ok(payload_bytes) => {
                             let message = Message::ManufacturerSpecific { manufacturer: manuf, payload: payload_bytes };
+                            let data = get_format("syx").unwrap().render(&[message]);
                             let mut f = fs::File::create(&outfile).expect("create file");
-                            f.write_all(&message.to_bytes()).expect("write to output file");
+                            f.write_all(&data).expect("write to output file");
                         }
                         Err(e) => {
                             eprintln!("{}", e);
@@ -414,8 +877,9 @@ fn run_make(manufacturer: &String, payload: &String, outfile: &PathBuf) {
                     match hex::decode(payload) {
                         Ok(payload_bytes) => {
                             let message = Message::ManufacturerSpecific { manufacturer: manuf, payload: payload_bytes };
+                            let data = get_format("syx").unwrap().render(&[message]);
                             let mut f = fs::File::create(&outfile).expect("create file");
-                            f.write_all(&message.to_bytes()).expect("write to output file");
+                            f.write_all(&data).expect("write to output file");
                         }
                         Err(e) => {
                             eprintln!("{}", e);
@@ -0,0 +1,134 @@
+// An mtree-style manifest for `syx split`, verified by `syx verify`.
+
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+// One emitted file's name, size, offset in the source file, and MD5 digest.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub length: usize,
+    pub offset: usize,
+    pub digest: String,
+}
+
+// A manifest for one `split` run: the digest of the whole source file,
+// plus one entry per file it was split into.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub source_digest: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            let text = serde_json::to_string_pretty(self).expect("serialize manifest");
+            fs::write(path, text)
+        }
+        else {
+            // mtree-style: one line per file, "name length=N offset=N digest=HEX".
+            let mut text = format!("# source digest={}\n", self.source_digest);
+            for entry in &self.entries {
+                text.push_str(&format!("{} length={} offset={} digest={}\n",
+                    entry.name, entry.length, entry.offset, entry.digest));
+            }
+            fs::write(path, text)
+        }
+    }
+
+    pub fn load(path: &Path) -> Option<Manifest> {
+        let text = fs::read_to_string(path).ok()?;
+
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            return serde_json::from_str(&text).ok();
+        }
+
+        let mut source_digest = String::new();
+        let mut entries = Vec::new();
+
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# source digest=") {
+                source_digest = rest.trim().to_string();
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() {
+                continue;
+            }
+
+            let name = parts[0].to_string();
+            let mut length = 0usize;
+            let mut offset = 0usize;
+            let mut digest = String::new();
+
+            for field in &parts[1..] {
+                if let Some(value) = field.strip_prefix("length=") {
+                    length = value.parse().ok()?;
+                }
+                else if let Some(value) = field.strip_prefix("offset=") {
+                    offset = value.parse().ok()?;
+                }
+                else if let Some(value) = field.strip_prefix("digest=") {
+                    digest = value.to_string();
+                }
+            }
+
+            entries.push(ManifestEntry { name, length, offset, digest });
+        }
+
+        Some(Manifest { source_digest, entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Manifest {
+        Manifest {
+            source_digest: "abcd1234".to_string(),
+            entries: vec![
+                ManifestEntry { name: "bank-001.syx".to_string(), length: 64, offset: 0, digest: "aaaa".to_string() },
+                ManifestEntry { name: "bank-002.syx".to_string(), length: 128, offset: 64, digest: "bbbb".to_string() },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_through_mtree_text() {
+        let path = std::env::temp_dir().join("syx-manifest-test.mtree");
+        sample().save(&path).expect("save manifest");
+
+        let loaded = Manifest::load(&path).expect("load manifest");
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.source_digest, "abcd1234");
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].name, "bank-001.syx");
+        assert_eq!(loaded.entries[0].length, 64);
+        assert_eq!(loaded.entries[1].offset, 64);
+        assert_eq!(loaded.entries[1].digest, "bbbb");
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let path = std::env::temp_dir().join("syx-manifest-test.json");
+        sample().save(&path).expect("save manifest");
+
+        let loaded = Manifest::load(&path).expect("load manifest");
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.source_digest, "abcd1234");
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[1].name, "bank-002.syx");
+    }
+
+    #[test]
+    fn load_returns_none_for_missing_file() {
+        let path = std::env::temp_dir().join("syx-manifest-does-not-exist.mtree");
+        assert!(Manifest::load(&path).is_none());
+    }
+}
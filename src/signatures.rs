@@ -0,0 +1,208 @@
+// A small device/patch fingerprint database for `identify`.
+
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use syxpack::Message;
+
+// How many leading payload bytes are considered when building or matching
+// a signature's prefix.
+const PREFIX_LENGTH: usize = 8;
+
+// A single device/patch fingerprint. `prefix` holds one entry per prefix
+// position: a fixed byte (0-255) if every example agreed on it, or -1 if
+// it varied (a wildcard). Plain `i16`s rather than `Option<u8>` so the
+// database round-trips through TOML, which has no way to represent a
+// bare `None` inside a `Vec`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signature {
+    pub name: String,
+    pub manufacturer: Vec<u8>,
+    pub prefix: Vec<i16>,
+    pub min_length: usize,
+    pub max_length: usize,
+}
+
+const WILDCARD: i16 = -1;
+
+// The result of matching a message against a signature database.
+pub struct SignatureMatch {
+    pub name: String,
+    pub score: usize,
+}
+
+impl Signature {
+    // Builds a signature by diffing the payload prefixes of one or more
+    // example messages. Positions that disagree across examples become
+    // wildcards; the prefix stops at the shortest example's length, so
+    // positions past it are not part of the prefix and are never compared.
+    pub fn generate(name: &str, examples: &[Message]) -> Option<Signature> {
+        let first_manufacturer = match examples.first()? {
+            Message::ManufacturerSpecific { manufacturer, .. } => manufacturer.to_bytes(),
+            Message::Universal { .. } => return None,
+        };
+
+        let payloads: Vec<&Vec<u8>> = examples.iter()
+            .filter_map(|message| match message {
+                Message::ManufacturerSpecific { payload, .. } => Some(payload),
+                Message::Universal { .. } => None,
+            })
+            .collect();
+
+        let shortest = payloads.iter().map(|p| p.len()).min().unwrap_or(0);
+        let prefix_length = PREFIX_LENGTH.min(shortest);
+
+        let mut prefix = Vec::with_capacity(prefix_length);
+        for i in 0..prefix_length {
+            let first_byte = payloads[0][i];
+            let agrees = payloads.iter().all(|payload| payload[i] == first_byte);
+            prefix.push(if agrees { first_byte as i16 } else { WILDCARD });
+        }
+
+        let min_length = payloads.iter().map(|p| p.len()).min().unwrap_or(0);
+        let max_length = payloads.iter().map(|p| p.len()).max().unwrap_or(0);
+
+        Some(Signature {
+            name: name.to_string(),
+            manufacturer: first_manufacturer,
+            prefix,
+            min_length,
+            max_length,
+        })
+    }
+
+    // Scores a message against this signature: `None` if the manufacturer,
+    // a fixed prefix byte, or the length range disagrees, otherwise
+    // `Some(count of matched fixed bytes)`.
+    fn score(&self, message: &Message) -> Option<usize> {
+        let (manufacturer, payload) = match message {
+            Message::ManufacturerSpecific { manufacturer, payload } => (manufacturer, payload),
+            Message::Universal { .. } => return None,
+        };
+
+        if manufacturer.to_bytes() != self.manufacturer {
+            return None;
+        }
+
+        if payload.len() < self.min_length || payload.len() > self.max_length {
+            return None;
+        }
+
+        let mut matched = 0;
+        for (i, expected) in self.prefix.iter().enumerate() {
+            if *expected != WILDCARD {
+                match payload.get(i) {
+                    Some(actual) if *actual as i16 == *expected => matched += 1,
+                    _ => return None,
+                }
+            }
+        }
+
+        Some(matched)
+    }
+}
+
+// A collection of signatures, as read from or written to a database file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SignatureDatabase {
+    pub signatures: Vec<Signature>,
+}
+
+impl SignatureDatabase {
+    pub fn load(path: &Path) -> Option<SignatureDatabase> {
+        let text = fs::read_to_string(path).ok()?;
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            serde_json::from_str(&text).ok()
+        }
+        else {
+            toml::from_str(&text).ok()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let text = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            serde_json::to_string_pretty(self).expect("serialize signature database")
+        }
+        else {
+            toml::to_string_pretty(self).expect("serialize signature database")
+        };
+        fs::write(path, text)
+    }
+
+    // Finds the best-scoring signature for a message, if any matches.
+    pub fn identify(&self, message: &Message) -> Option<SignatureMatch> {
+        self.signatures.iter()
+            .filter_map(|signature| signature.score(message).map(|score| SignatureMatch {
+                name: signature.name.clone(),
+                score,
+            }))
+            .max_by_key(|m| m.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syxpack::Manufacturer;
+
+    fn message(manufacturer_id: u8, payload: Vec<u8>) -> Message {
+        Message::ManufacturerSpecific {
+            manufacturer: Manufacturer::new(vec![manufacturer_id]).unwrap(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn generate_accepts_examples_of_different_lengths() {
+        let short = message(0x42, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let long = message(0x42, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+
+        let signature = Signature::generate("test", &[short.clone(), long.clone()]).unwrap();
+
+        assert_eq!(signature.min_length, 8);
+        assert_eq!(signature.max_length, 12);
+        assert!(signature.score(&short).is_some());
+        assert!(signature.score(&long).is_some());
+    }
+
+    #[test]
+    fn score_rejects_wrong_manufacturer() {
+        let example = message(0x42, vec![1, 2, 3, 4]);
+        let signature = Signature::generate("test", &[example]).unwrap();
+
+        let other = message(0x43, vec![1, 2, 3, 4]);
+        assert!(signature.score(&other).is_none());
+    }
+
+    #[test]
+    fn score_rejects_length_outside_range() {
+        let short = message(0x42, vec![1, 2, 3, 4]);
+        let long = message(0x42, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let signature = Signature::generate("test", &[short, long]).unwrap();
+
+        let too_short = message(0x42, vec![1, 2, 3]);
+        assert!(signature.score(&too_short).is_none());
+    }
+
+    #[test]
+    fn prefix_becomes_wildcard_where_examples_disagree() {
+        let a = message(0x42, vec![1, 2, 3]);
+        let b = message(0x42, vec![1, 9, 3]);
+        let signature = Signature::generate("test", &[a, b]).unwrap();
+
+        assert_eq!(signature.prefix, vec![1, WILDCARD, 3]);
+    }
+
+    #[test]
+    fn database_round_trips_through_toml() {
+        let example = message(0x42, vec![1, 2, 3]);
+        let signature = Signature::generate("test", &[example]).unwrap();
+        let db = SignatureDatabase { signatures: vec![signature] };
+
+        let text = toml::to_string_pretty(&db).expect("serialize");
+        let parsed: SignatureDatabase = toml::from_str(&text).expect("deserialize");
+
+        assert_eq!(parsed.signatures.len(), 1);
+        assert_eq!(parsed.signatures[0].name, "test");
+    }
+}
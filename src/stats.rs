@@ -0,0 +1,145 @@
+// Byte-frequency and 7-bit validity analysis for `syx stats`.
+
+use serde::Serialize;
+use syxpack::Message;
+
+// The offset of an out-of-range payload byte, and the message it was found in.
+#[derive(Debug, Serialize)]
+pub struct InvalidByte {
+    pub message_index: usize,
+    pub offset: usize,
+    pub value: u8,
+}
+
+// A summary of the payload bytes across every message in a file.
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub message_count: usize,
+    pub histogram: Vec<usize>, // indexed by byte value, 0..=255
+    pub most_common_bytes: Vec<(u8, usize)>,
+    pub total_payload_size: usize,
+    pub average_payload_size: f64,
+    pub min_payload_size: usize,
+    pub max_payload_size: usize,
+    pub invalid_bytes: Vec<InvalidByte>,
+}
+
+impl Stats {
+    pub fn gather(messages: &[Message]) -> Stats {
+        let mut histogram = vec![0usize; 256];
+        let mut invalid_bytes = Vec::new();
+        let mut sizes = Vec::with_capacity(messages.len());
+
+        for (message_index, message) in messages.iter().enumerate() {
+            let payload = match message {
+                Message::ManufacturerSpecific { payload, .. } => payload,
+                Message::Universal { payload, .. } => payload,
+            };
+
+            sizes.push(payload.len());
+
+            for (offset, &byte) in payload.iter().enumerate() {
+                histogram[byte as usize] += 1;
+                if byte & 0x80 != 0 {
+                    invalid_bytes.push(InvalidByte { message_index, offset, value: byte });
+                }
+            }
+        }
+
+        let total_payload_size: usize = sizes.iter().sum();
+        let average_payload_size = if sizes.is_empty() {
+            0.0
+        }
+        else {
+            total_payload_size as f64 / sizes.len() as f64
+        };
+
+        let mut most_common_bytes: Vec<(u8, usize)> = histogram.iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(value, &count)| (value as u8, count))
+            .collect();
+        most_common_bytes.sort_by(|a, b| b.1.cmp(&a.1));
+        most_common_bytes.truncate(10);
+
+        Stats {
+            message_count: messages.len(),
+            histogram,
+            most_common_bytes,
+            total_payload_size,
+            average_payload_size,
+            min_payload_size: sizes.iter().copied().min().unwrap_or(0),
+            max_payload_size: sizes.iter().copied().max().unwrap_or(0),
+            invalid_bytes,
+        }
+    }
+
+    pub fn print_table(&self) {
+        println!("Messages: {}", self.message_count);
+        println!("Total payload size: {} bytes", self.total_payload_size);
+        println!("Average payload size: {:.1} bytes", self.average_payload_size);
+        println!("Min/max payload size: {}/{} bytes", self.min_payload_size, self.max_payload_size);
+        println!();
+
+        println!("Most common byte values:");
+        for (value, count) in &self.most_common_bytes {
+            println!("  {:02X}: {}", value, count);
+        }
+        println!();
+
+        if self.invalid_bytes.is_empty() {
+            println!("No invalid (high-bit-set) payload bytes found");
+        }
+        else {
+            println!("Found {} invalid (high-bit-set) payload byte(s):", self.invalid_bytes.len());
+            for invalid in &self.invalid_bytes {
+                println!("  message {}, offset {:06X}: {:02X}", invalid.message_index + 1, invalid.offset, invalid.value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syxpack::Manufacturer;
+
+    fn message(payload: Vec<u8>) -> Message {
+        Message::ManufacturerSpecific {
+            manufacturer: Manufacturer::new(vec![0x42]).unwrap(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn gather_reports_size_distribution() {
+        let stats = Stats::gather(&[message(vec![1, 2, 3]), message(vec![1, 2, 3, 4, 5])]);
+
+        assert_eq!(stats.message_count, 2);
+        assert_eq!(stats.total_payload_size, 8);
+        assert_eq!(stats.min_payload_size, 3);
+        assert_eq!(stats.max_payload_size, 5);
+        assert_eq!(stats.average_payload_size, 4.0);
+    }
+
+    #[test]
+    fn gather_flags_high_bit_set_bytes() {
+        let stats = Stats::gather(&[message(vec![0x01, 0x80, 0x7F, 0xFF])]);
+
+        assert_eq!(stats.invalid_bytes.len(), 2);
+        assert_eq!(stats.invalid_bytes[0].offset, 1);
+        assert_eq!(stats.invalid_bytes[0].value, 0x80);
+        assert_eq!(stats.invalid_bytes[1].offset, 3);
+        assert_eq!(stats.invalid_bytes[1].value, 0xFF);
+    }
+
+    #[test]
+    fn gather_handles_no_messages() {
+        let stats = Stats::gather(&[]);
+
+        assert_eq!(stats.message_count, 0);
+        assert_eq!(stats.total_payload_size, 0);
+        assert_eq!(stats.average_payload_size, 0.0);
+        assert!(stats.invalid_bytes.is_empty());
+    }
+}